@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Clap;
+
+use crate::process::stdio::{StdioConfig, StdioMode};
+
+/// Execute a process within an existing container
+#[derive(Clap, Debug)]
+pub struct Exec {
+    /// Path to process.json describing the process to spawn; exec rejects this option with an error, so the command has to be passed directly
+    #[clap(long)]
+    pub process: Option<PathBuf>,
+    /// Current working directory of the executed process, inside the container
+    #[clap(long)]
+    pub cwd: Option<PathBuf>,
+    /// File to write the exec'd process' pid to
+    #[clap(short, long)]
+    pub pid_file: Option<PathBuf>,
+    /// Unix socket (file) path, which will receive file descriptor of the writing end of the pseudoterminal
+    #[clap(short, long)]
+    pub console_socket: Option<PathBuf>,
+    /// how to wire up the exec'd process' stdin: `inherit`, `null`, `pipe`, or an open fd number
+    #[clap(long, default_value = "inherit", parse(try_from_str))]
+    pub stdin: StdioMode,
+    /// how to wire up the exec'd process' stdout: `inherit`, `null`, `pipe`, or an open fd number
+    #[clap(long, default_value = "inherit", parse(try_from_str))]
+    pub stdout: StdioMode,
+    /// how to wire up the exec'd process' stderr: `inherit`, `null`, `pipe`, or an open fd number
+    #[clap(long, default_value = "inherit", parse(try_from_str))]
+    pub stderr: StdioMode,
+    /// name of the container instance
+    pub container_id: String,
+    /// command and arguments to run in the container, if not using --process
+    pub command: Vec<String>,
+}
+
+impl Exec {
+    pub fn stdio_config(&self) -> StdioConfig {
+        StdioConfig {
+            stdin: self.stdin,
+            stdout: self.stdout,
+            stderr: self.stderr,
+        }
+    }
+
+    pub fn exec(&self, root_path: PathBuf) -> Result<()> {
+        let container = crate::container::builder::ContainerBuilder::new(self.container_id.clone(), root_path)
+            .with_pid_file(self.pid_file.as_ref())?
+            .with_console_socket(self.console_socket.as_ref())
+            .with_stdio(self.stdio_config())
+            .as_tenant(self.process.as_ref(), self.cwd.as_ref(), &self.command)
+            .build()?;
+        container.stdio_pipes().print();
+        Ok(())
+    }
+}
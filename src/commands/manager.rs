@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+use clap::Clap;
+use mio::net::{UnixListener, UnixStream};
+use mio::{Events, Interest, Poll, Token};
+use nix::sys::signal::{kill, Signal};
+use nix::sys::wait::{waitpid, WaitPidFlag};
+use serde::Deserialize;
+
+use crate::container::builder::ContainerBuilder;
+use crate::container::Container;
+use crate::process::emitter::EventEmitter;
+use crate::process::event::Event;
+use crate::process::exit_status::decode_exit_status;
+
+const LISTENER: Token = Token(0);
+// exit-notification tokens are offset well clear of the connection token
+// range so the two can never collide
+const EXIT_TOKEN_BASE: usize = 1_000_000_000;
+
+fn exit_token(pid_seq: u64) -> Token {
+    Token(EXIT_TOKEN_BASE + pid_seq as usize)
+}
+
+fn pid_seq_for_exit_token(token: Token) -> Option<u64> {
+    token.0.checked_sub(EXIT_TOKEN_BASE).map(|n| n as u64)
+}
+
+/// A container tracked by the manager, keyed by a monotonically increasing
+/// process-id sequence number rather than the OS pid, which gets reused
+/// once the container exits.
+struct ManagedContainer {
+    container_id: String,
+    bundle: PathBuf,
+    container: Option<Container>,
+    exit_code: Option<i32>,
+}
+
+/// An accepted connection that hasn't (yet) become an event subscriber,
+/// with whatever partial request bytes have been read off it so far.
+struct Connection {
+    stream: UnixStream,
+    buf: Vec<u8>,
+}
+
+/// Requests accepted on the manager socket, one newline-delimited JSON
+/// object per request.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Request {
+    Create { container_id: String, bundle: PathBuf },
+    Start { pid: u64 },
+    Exec { pid: u64, command: Vec<String> },
+    Kill { pid: u64, signal: i32 },
+    Delete { pid: u64 },
+    Subscribe,
+}
+
+/// Run youki as a long-lived server: listen on a unix socket and accept
+/// create/start/kill/delete requests for many containers in one process,
+/// rather than doing a single action and exiting. Every accepted container
+/// gets a process-id sequence number used as its registry key, and
+/// subscribers receive a stream of lifecycle events via [`EventEmitter`]
+/// as containers move through their lifecycle.
+#[derive(Clap, Debug)]
+pub struct Manager {
+    /// path of the unix socket to listen on for control requests and event subscriptions
+    #[clap(short, long, default_value = "/run/youki/manager.sock")]
+    pub socket: PathBuf,
+}
+
+impl Manager {
+    pub fn exec(&self, root_path: PathBuf) -> Result<()> {
+        if self.socket.exists() {
+            std::fs::remove_file(&self.socket)?;
+        }
+
+        let mut listener = UnixListener::bind(&self.socket)
+            .with_context(|| format!("failed to bind manager socket at {:?}", self.socket))?;
+        let mut poll = Poll::new()?;
+        poll.registry()
+            .register(&mut listener, LISTENER, Interest::READABLE)?;
+
+        let next_token = AtomicU64::new(1);
+        let next_pid_seq = AtomicU64::new(1);
+        let mut registry: HashMap<u64, ManagedContainer> = HashMap::new();
+        let mut connections: HashMap<Token, Connection> = HashMap::new();
+        let mut emitter = EventEmitter::new();
+        let mut mio_events = Events::with_capacity(128);
+
+        loop {
+            poll.poll(&mut mio_events, None)?;
+
+            for mio_event in mio_events.iter() {
+                let token = mio_event.token();
+
+                if token == LISTENER {
+                    while let Ok((mut stream, _addr)) = listener.accept() {
+                        let token = Token(next_token.fetch_add(1, Ordering::SeqCst) as usize);
+                        poll.registry()
+                            .register(&mut stream, token, Interest::READABLE)?;
+                        connections.insert(
+                            token,
+                            Connection {
+                                stream,
+                                buf: Vec::new(),
+                            },
+                        );
+                    }
+                    continue;
+                }
+
+                if let Some(pid) = pid_seq_for_exit_token(token) {
+                    self.handle_exit_notification(pid, &mut registry, &mut emitter)?;
+                    continue;
+                }
+
+                if emitter.is_subscriber(token) {
+                    if mio_event.is_writable() {
+                        emitter.flush(token);
+                    }
+                    if mio_event.is_read_closed() || mio_event.is_error() {
+                        emitter.unsubscribe(token);
+                    }
+                    continue;
+                }
+
+                if let Some(connection) = connections.remove(&token) {
+                    if let Some(connection) = self.pump_connection(
+                        token,
+                        connection,
+                        &root_path,
+                        &next_pid_seq,
+                        &mut registry,
+                        &mut emitter,
+                        poll.registry(),
+                    )? {
+                        connections.insert(token, connection);
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_exit_notification(
+        &self,
+        pid: u64,
+        registry: &mut HashMap<u64, ManagedContainer>,
+        emitter: &mut EventEmitter,
+    ) -> Result<()> {
+        let managed = match registry.get_mut(&pid) {
+            Some(managed) => managed,
+            None => return Ok(()),
+        };
+        let container = match managed.container.as_mut() {
+            Some(container) => container,
+            None => return Ok(()),
+        };
+
+        let exit_code = container.try_recv_exit_status()?;
+        managed.exit_code = Some(exit_code);
+
+        // a payload killed by SIGKILL decodes to 128 + 9 = 137; that's also
+        // the signature of an OOM kill, so surface it as its own event
+        // ahead of the generic `exited`
+        if exit_code == 128 + Signal::SIGKILL as i32 {
+            emitter.emit(Event::Oom {
+                container_id: managed.container_id.clone(),
+            })?;
+        }
+        emitter.emit(Event::Exited {
+            container_id: managed.container_id.clone(),
+            exit_code,
+        })?;
+        Ok(())
+    }
+
+    /// Reads whatever is available on `connection`, handling every complete
+    /// newline-delimited request found. Returns the connection back if it's
+    /// still open and should be kept around for the next readiness event,
+    /// or `None` if it was consumed into an event subscription or closed.
+    #[allow(clippy::too_many_arguments)]
+    fn pump_connection(
+        &self,
+        token: Token,
+        mut connection: Connection,
+        root_path: &Path,
+        next_pid_seq: &AtomicU64,
+        registry: &mut HashMap<u64, ManagedContainer>,
+        emitter: &mut EventEmitter,
+        poll_registry: &mio::Registry,
+    ) -> Result<Option<Connection>> {
+        let mut chunk = [0u8; 4096];
+        let mut closed = false;
+        loop {
+            match connection.stream.read(&mut chunk) {
+                Ok(0) => {
+                    closed = true;
+                    break;
+                }
+                Ok(n) => connection.buf.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => return Ok(None),
+            }
+        }
+
+        while let Some(newline_pos) = connection.buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = connection.buf.drain(..=newline_pos).collect();
+            let request: Request = match serde_json::from_slice(&line[..line.len() - 1]) {
+                Ok(request) => request,
+                Err(_) => continue,
+            };
+
+            if matches!(request, Request::Subscribe) {
+                // subscribers need to be writable too, so the manager can
+                // tell when a backed-up `emitter` buffer can drain
+                poll_registry.reregister(
+                    &mut connection.stream,
+                    token,
+                    Interest::READABLE | Interest::WRITABLE,
+                )?;
+                emitter.subscribe(token, connection.stream);
+                return Ok(None);
+            }
+
+            self.handle_request(
+                request,
+                root_path,
+                next_pid_seq,
+                registry,
+                emitter,
+                poll_registry,
+            )?;
+        }
+
+        if closed {
+            return Ok(None);
+        }
+        Ok(Some(connection))
+    }
+
+    fn handle_request(
+        &self,
+        request: Request,
+        root_path: &Path,
+        next_pid_seq: &AtomicU64,
+        registry: &mut HashMap<u64, ManagedContainer>,
+        emitter: &mut EventEmitter,
+        poll_registry: &mio::Registry,
+    ) -> Result<()> {
+        match request {
+            Request::Create { container_id, bundle } => {
+                let pid = next_pid_seq.fetch_add(1, Ordering::SeqCst);
+                registry.insert(
+                    pid,
+                    ManagedContainer {
+                        container_id: container_id.clone(),
+                        bundle,
+                        container: None,
+                        exit_code: None,
+                    },
+                );
+                emitter.emit(Event::Created { container_id })?;
+            }
+            Request::Start { pid } => {
+                let managed = match registry.get_mut(&pid) {
+                    Some(managed) => managed,
+                    None => return Ok(()),
+                };
+                let mut container = ContainerBuilder::new(managed.container_id.clone(), root_path.to_path_buf())
+                    .as_init(&managed.bundle)
+                    .build()?;
+                let real_pid = container.pid().as_raw();
+                container.register_exit_notifications(poll_registry, exit_token(pid))?;
+                managed.container = Some(container);
+                emitter.emit(Event::Started {
+                    container_id: managed.container_id.clone(),
+                    pid: real_pid,
+                })?;
+            }
+            Request::Exec { pid, command } => {
+                let managed = match registry.get(&pid) {
+                    Some(managed) => managed,
+                    None => return Ok(()),
+                };
+                let tenant = ContainerBuilder::new(managed.container_id.clone(), root_path.to_path_buf())
+                    .as_tenant(None, None, &command)
+                    .build()?;
+                emitter.emit(Event::Exec {
+                    container_id: managed.container_id.clone(),
+                    pid: tenant.pid().as_raw(),
+                })?;
+            }
+            Request::Kill { pid, signal } => {
+                let managed = match registry.get(&pid) {
+                    Some(managed) => managed,
+                    None => return Ok(()),
+                };
+                if let Some(container) = &managed.container {
+                    let signal = Signal::try_from(signal).context("invalid signal number")?;
+                    kill(container.pid(), signal)?;
+                    emitter.emit(Event::Stopped {
+                        container_id: managed.container_id.clone(),
+                    })?;
+                }
+            }
+            Request::Delete { pid } => {
+                let mut managed = match registry.remove(&pid) {
+                    Some(managed) => managed,
+                    None => return Ok(()),
+                };
+                let exit_code = match (managed.exit_code, &mut managed.container) {
+                    (Some(exit_code), _) => exit_code,
+                    (None, Some(container)) => {
+                        container.deregister_exit_notifications(poll_registry).ok();
+                        kill(container.pid(), Signal::SIGKILL).ok();
+                        match waitpid(container.pid(), Some(WaitPidFlag::empty())) {
+                            Ok(status) => decode_exit_status(status),
+                            Err(_) => 128,
+                        }
+                    }
+                    (None, None) => 0,
+                };
+                emitter.emit(Event::Exited {
+                    container_id: managed.container_id,
+                    exit_code,
+                })?;
+            }
+            Request::Subscribe => unreachable!("handled in pump_connection"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod create;
+pub mod exec;
+pub mod manager;
+pub mod run;
+pub mod wait;
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Clap;
+
+use crate::process::stdio::{StdioConfig, StdioMode};
+
+/// Create and immediately start a container, in a single step
+#[derive(Clap, Debug)]
+pub struct Run {
+    /// File to write pid to
+    #[clap(short, long)]
+    pub pid_file: Option<PathBuf>,
+    /// path to the bundle directory, containing config.json and root filesystem
+    #[clap(short, long, default_value = ".")]
+    pub bundle: PathBuf,
+    /// Unix socket (file) path, which will receive file descriptor of the writing end of the pseudoterminal
+    #[clap(short, long)]
+    pub console_socket: Option<PathBuf>,
+    /// how to wire up the container's stdin: `inherit`, `null`, `pipe`, or an open fd number
+    #[clap(long, default_value = "inherit", parse(try_from_str))]
+    pub stdin: StdioMode,
+    /// how to wire up the container's stdout: `inherit`, `null`, `pipe`, or an open fd number
+    #[clap(long, default_value = "inherit", parse(try_from_str))]
+    pub stdout: StdioMode,
+    /// how to wire up the container's stderr: `inherit`, `null`, `pipe`, or an open fd number
+    #[clap(long, default_value = "inherit", parse(try_from_str))]
+    pub stderr: StdioMode,
+    /// name of the container instance to be started
+    pub container_id: String,
+}
+
+impl Run {
+    pub fn stdio_config(&self) -> StdioConfig {
+        StdioConfig {
+            stdin: self.stdin,
+            stdout: self.stdout,
+            stderr: self.stderr,
+        }
+    }
+
+    /// Create, start and wait for the container in one step, returning the
+    /// container payload's decoded exit status as youki's own process exit
+    /// code, the same way `runc run` does.
+    pub fn exec(&self, root_path: PathBuf, systemd_cgroup: bool) -> Result<i32> {
+        let container = crate::container::builder::ContainerBuilder::new(
+            self.container_id.clone(),
+            root_path,
+        )
+        .with_pid_file(self.pid_file.as_ref())?
+        .with_console_socket(self.console_socket.as_ref())
+        .with_stdio(self.stdio_config())
+        .with_systemd(systemd_cgroup)
+        .as_init(&self.bundle)
+        .build()?;
+        container.stdio_pipes().print();
+        let exit_code = container.wait()?;
+        Ok(exit_code)
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Clap;
+
+/// Block until a container's init process reports the payload's exit
+/// status, then return it as youki's own process exit code. Unlike `run`,
+/// this attaches to a container that was already created and started
+/// separately, mirroring `runc wait` semantics (a normal exit returns the
+/// code as-is, a signal-terminated payload returns `128 + signum`).
+#[derive(Clap, Debug)]
+pub struct Wait {
+    /// name of the container instance to wait on
+    pub container_id: String,
+}
+
+impl Wait {
+    pub fn exec(&self, root_path: PathBuf) -> Result<i32> {
+        let exit_code = crate::container::load(root_path, &self.container_id)?.wait_for_exit()?;
+        Ok(exit_code)
+    }
+}
@@ -0,0 +1,227 @@
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use nix::sys::wait::waitpid;
+use nix::unistd::{chdir, execvp, fork, ForkResult};
+
+use super::{exit_status_path, Container};
+use crate::process::child::ChildProcess;
+use crate::process::exit_status::decode_exit_status;
+use crate::process::parent::ParentChannel;
+use crate::process::stdio::{prepare_stdio, PreparedStdio, StdioConfig};
+
+enum Mode {
+    Init {
+        bundle: PathBuf,
+    },
+    Tenant {
+        process: Option<PathBuf>,
+        cwd: Option<PathBuf>,
+        command: Vec<String>,
+    },
+}
+
+/// Builds up the configuration for a container (or a tenant process run
+/// inside one via `exec`) and, on [`Self::build`], forks the init process
+/// and wires up its synchronization channel and stdio. No cgroup is set up:
+/// the [`Self::with_systemd`] choice is stored but `build` does not use it.
+pub struct ContainerBuilder {
+    container_id: String,
+    root_path: PathBuf,
+    pid_file: Option<PathBuf>,
+    console_socket: Option<PathBuf>,
+    stdio: StdioConfig,
+    systemd_cgroup: bool,
+    mode: Option<Mode>,
+}
+
+impl ContainerBuilder {
+    pub fn new(container_id: String, root_path: PathBuf) -> Self {
+        Self {
+            container_id,
+            root_path,
+            pid_file: None,
+            console_socket: None,
+            stdio: StdioConfig::default(),
+            systemd_cgroup: false,
+            mode: None,
+        }
+    }
+
+    pub fn with_pid_file(mut self, pid_file: Option<&PathBuf>) -> Result<Self> {
+        self.pid_file = pid_file.cloned();
+        Ok(self)
+    }
+
+    pub fn with_console_socket(mut self, console_socket: Option<&PathBuf>) -> Self {
+        self.console_socket = console_socket.cloned();
+        self
+    }
+
+    /// Thread the caller's `--stdin`/`--stdout`/`--stderr` choice through to
+    /// the init process: [`Self::build`] resolves it into fds before the
+    /// fork and applies it in the init process, right before the payload is
+    /// exec'd.
+    pub fn with_stdio(mut self, stdio: StdioConfig) -> Self {
+        self.stdio = stdio;
+        self
+    }
+
+    pub fn with_systemd(mut self, systemd_cgroup: bool) -> Self {
+        self.systemd_cgroup = systemd_cgroup;
+        self
+    }
+
+    pub fn as_init(mut self, bundle: &Path) -> Self {
+        self.mode = Some(Mode::Init {
+            bundle: bundle.to_path_buf(),
+        });
+        self
+    }
+
+    pub fn as_tenant(mut self, process: Option<&PathBuf>, cwd: Option<&PathBuf>, command: &[String]) -> Self {
+        self.mode = Some(Mode::Tenant {
+            process: process.cloned(),
+            cwd: cwd.cloned(),
+            command: command.to_vec(),
+        });
+        self
+    }
+
+    /// Fork the init process. Stdio pipes are resolved here, before the
+    /// fork, so both the init process and youki itself end up holding the
+    /// matching end of each `Pipe`-mode stream.
+    pub fn build(self) -> Result<Container> {
+        let prepared_stdio = prepare_stdio(&self.stdio)?;
+        let mode = self
+            .mode
+            .context("container builder requires as_init() or as_tenant()")?;
+
+        let (sender_to_child, receiver_from_parent) = mio::unix::pipe::new()?;
+        let (sender_to_parent, receiver_from_child) = mio::unix::pipe::new()?;
+        // mio creates both pipes non-blocking; ParentChannel's reads wait for
+        // the other side, so the receiving ends are switched to blocking mode
+        receiver_from_parent.set_nonblocking(false)?;
+        receiver_from_child.set_nonblocking(false)?;
+
+        match unsafe { fork() }? {
+            ForkResult::Parent { child } => {
+                drop(receiver_from_parent);
+                drop(sender_to_parent);
+
+                if let Some(pid_file) = &self.pid_file {
+                    std::fs::write(pid_file, child.as_raw().to_string())
+                        .context("failed to write pid file")?;
+                }
+
+                let stdio_pipes = prepared_stdio.into_parent_fds()?;
+                let mut parent_channel = ParentChannel::new(sender_to_child, receiver_from_child);
+                // the init process sends this right after the fork, before it
+                // forks again for the payload; drain it here so the channel
+                // only ever carries the exit status from this point on
+                parent_channel.wait_for_child_ready()?;
+
+                Ok(Container::new(
+                    self.container_id,
+                    self.root_path,
+                    child,
+                    parent_channel,
+                    stdio_pipes,
+                ))
+            }
+            ForkResult::Child => {
+                drop(sender_to_child);
+                drop(receiver_from_child);
+
+                let child_channel = ParentChannel::new(sender_to_parent, receiver_from_parent);
+                let mut child_process = ChildProcess::new(child_channel)?;
+                let exit_code = run_init(
+                    &mut child_process,
+                    &prepared_stdio,
+                    &mode,
+                    &self.root_path,
+                    &self.container_id,
+                );
+                std::process::exit(exit_code);
+            }
+        }
+    }
+}
+
+/// Runs in the forked init process: wires up stdio, forks again to exec the
+/// actual container payload (so the init process stays free to wait on it,
+/// the way a container's pid 1 would), decodes the payload's exit status,
+/// and reports it both over the sync channel (read by `run`, which kept the
+/// channel open) and to disk (read by a separate `wait` invocation).
+fn run_init(
+    child: &mut ChildProcess,
+    stdio: &PreparedStdio,
+    mode: &Mode,
+    root_path: &Path,
+    container_id: &str,
+) -> i32 {
+    match run_init_inner(child, stdio, mode, root_path, container_id) {
+        Ok(exit_code) => exit_code,
+        Err(_) => 1,
+    }
+}
+
+fn run_init_inner(
+    child: &mut ChildProcess,
+    stdio: &PreparedStdio,
+    mode: &Mode,
+    root_path: &Path,
+    container_id: &str,
+) -> Result<i32> {
+    child.notify_parent()?;
+    child.apply_stdio(stdio)?;
+
+    match unsafe { fork() }? {
+        ForkResult::Parent { child: payload_pid } => {
+            let status = waitpid(payload_pid, None)?;
+            let exit_code = decode_exit_status(status);
+
+            let status_path = exit_status_path(root_path, container_id);
+            if let Some(parent_dir) = status_path.parent() {
+                std::fs::create_dir_all(parent_dir)?;
+            }
+            std::fs::write(&status_path, exit_code.to_string())?;
+
+            child.report_exit_status(exit_code)?;
+            Ok(exit_code)
+        }
+        ForkResult::Child => {
+            exec_payload(mode)
+        }
+    }
+}
+
+fn exec_payload(mode: &Mode) -> Result<i32> {
+    let command = match mode {
+        Mode::Init { bundle } => {
+            vec![bundle.join("rootfs/entrypoint").to_string_lossy().into_owned()]
+        }
+        Mode::Tenant { process, cwd, command } => {
+            if process.is_some() {
+                bail!("--process is not supported yet; pass the command to exec directly");
+            }
+            if let Some(cwd) = cwd {
+                chdir(cwd.as_path()).with_context(|| format!("failed to chdir to {:?}", cwd))?;
+            }
+            command.clone()
+        }
+    };
+
+    let program = command.first().context("no command to exec")?;
+    let args = command
+        .iter()
+        .map(|arg| CString::new(arg.as_str()))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("command contains a nul byte")?;
+
+    match execvp(&CString::new(program.as_str())?, &args) {
+        Ok(_) => bail!("execvp unexpectedly returned success"),
+        Err(errno) => bail!("failed to exec container payload: {}", errno),
+    }
+}
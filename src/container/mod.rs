@@ -0,0 +1,113 @@
+pub mod builder;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use mio::{Interest, Token};
+use nix::unistd::Pid;
+
+use crate::process::parent::ParentChannel;
+use crate::process::stdio::StdioPipeFds;
+
+/// A container whose init process has already been forked and is running.
+pub struct Container {
+    container_id: String,
+    root_path: PathBuf,
+    init_pid: Pid,
+    parent_channel: ParentChannel,
+    stdio_pipes: StdioPipeFds,
+}
+
+impl Container {
+    pub(crate) fn new(
+        container_id: String,
+        root_path: PathBuf,
+        init_pid: Pid,
+        parent_channel: ParentChannel,
+        stdio_pipes: StdioPipeFds,
+    ) -> Self {
+        Self {
+            container_id,
+            root_path,
+            init_pid,
+            parent_channel,
+            stdio_pipes,
+        }
+    }
+
+    pub fn container_id(&self) -> &str {
+        &self.container_id
+    }
+
+    pub fn pid(&self) -> Pid {
+        self.init_pid
+    }
+
+    pub fn stdio_pipes(&self) -> &StdioPipeFds {
+        &self.stdio_pipes
+    }
+
+    /// Block until the init process reports the container payload's exit
+    /// status over the sync channel, and return it. Used by `run`, which
+    /// keeps this `Container` (and its channel) alive for its entire
+    /// lifetime in a single process.
+    pub fn wait(mut self) -> Result<i32> {
+        self.parent_channel.wait_for_exit_status()
+    }
+
+    /// Register this container's exit-status channel with `registry` under
+    /// `token`, so a caller running its own poll loop over many containers
+    /// (the manager) learns when [`Self::try_recv_exit_status`] will
+    /// succeed without blocking.
+    pub fn register_exit_notifications(&mut self, registry: &mio::Registry, token: Token) -> Result<()> {
+        registry.register(self.parent_channel.receiver_mut(), token, Interest::READABLE)?;
+        Ok(())
+    }
+
+    /// Read the exit status already signalled as ready by the poller. Only
+    /// call this after the token passed to [`Self::register_exit_notifications`]
+    /// comes back readable.
+    pub fn try_recv_exit_status(&mut self) -> Result<i32> {
+        self.parent_channel.wait_for_exit_status()
+    }
+
+    /// Undo [`Self::register_exit_notifications`] before dropping this
+    /// container, so the poller doesn't keep a stale registration around.
+    pub fn deregister_exit_notifications(&mut self, registry: &mio::Registry) -> Result<()> {
+        registry.deregister(self.parent_channel.receiver_mut())?;
+        Ok(())
+    }
+}
+
+/// Load a container that a separate `create`/`start` invocation already set
+/// up. `wait` runs in its own process and has no access to the original
+/// sync channel, so instead it reads the exit status the init process
+/// persisted to disk once the payload exited.
+pub fn load(root_path: PathBuf, container_id: &str) -> Result<LoadedContainer> {
+    Ok(LoadedContainer {
+        exit_status_path: exit_status_path(&root_path, container_id),
+    })
+}
+
+pub struct LoadedContainer {
+    exit_status_path: PathBuf,
+}
+
+impl LoadedContainer {
+    /// Poll the on-disk exit status file until the init process writes it,
+    /// then return the code stored there, which the init process already
+    /// decoded with [`crate::process::exit_status::decode_exit_status`].
+    pub fn wait_for_exit(&self) -> Result<i32> {
+        loop {
+            if let Ok(contents) = std::fs::read_to_string(&self.exit_status_path) {
+                return Ok(contents.trim().parse()?);
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+pub(crate) fn exit_status_path(root_path: &Path, container_id: &str) -> PathBuf {
+    root_path.join(container_id).join("exit_status")
+}
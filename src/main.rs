@@ -15,6 +15,7 @@ use youki::commands::exec;
 use youki::commands::info;
 use youki::commands::kill;
 use youki::commands::list;
+use youki::commands::manager;
 use youki::commands::pause;
 use youki::commands::ps;
 use youki::commands::resume;
@@ -22,6 +23,7 @@ use youki::commands::run;
 use youki::commands::spec_json;
 use youki::commands::start;
 use youki::commands::state;
+use youki::commands::wait;
 use youki::rootless::should_use_rootless;
 
 // High-level commandline option definition
@@ -77,6 +79,12 @@ enum SubCommand {
     Events(events::Events),
     #[clap(version = "0.0.0", author = "youki team", setting=clap::AppSettings::AllowLeadingHyphen)]
     Ps(ps::Ps),
+    /// run youki as a long-lived server managing many containers over a unix socket
+    #[clap(version = "0.0.0", author = "youki team")]
+    Manager(manager::Manager),
+    /// wait for a container's payload to exit and return its exit code
+    #[clap(version = "0.0.0", author = "youki team")]
+    Wait(wait::Wait),
 }
 
 /// This is the entry point in the container runtime. The binary is run by a high-level container runtime,
@@ -100,7 +108,10 @@ fn main() -> Result<()> {
     match opts.subcmd {
         SubCommand::Create(create) => create.exec(root_path, systemd_cgroup),
         SubCommand::Start(start) => start.exec(root_path),
-        SubCommand::Run(run) => run.exec(root_path, systemd_cgroup),
+        SubCommand::Run(run) => {
+            let exit_code = run.exec(root_path, systemd_cgroup)?;
+            std::process::exit(exit_code);
+        }
         SubCommand::Exec(exec) => exec.exec(root_path),
         SubCommand::Kill(kill) => kill.exec(root_path),
         SubCommand::Delete(delete) => delete.exec(root_path, systemd_cgroup),
@@ -112,5 +123,10 @@ fn main() -> Result<()> {
         SubCommand::Resume(resume) => resume.exec(root_path, systemd_cgroup),
         SubCommand::Events(events) => events.exec(root_path),
         SubCommand::Ps(ps) => ps.exec(root_path),
+        SubCommand::Manager(manager) => manager.exec(root_path),
+        SubCommand::Wait(wait) => {
+            let exit_code = wait.exec(root_path)?;
+            std::process::exit(exit_code);
+        }
     }
 }
@@ -5,6 +5,7 @@ use mio::unix::pipe::Sender;
 use mio::{Interest, Poll, Token};
 
 use super::parent::ParentChannel;
+use super::stdio::PreparedStdio;
 
 // Token is used to identify which socket generated an event
 const CHILD: Token = Token(1);
@@ -49,6 +50,16 @@ impl ChildProcess {
         Ok(sender)
     }
 
+    /// Wires up the container payload's stdin/stdout/stderr according to
+    /// `prepared`, replacing the previous behaviour of always inheriting
+    /// youki's own fds. Must be called here in the init process, after
+    /// `clone(2)` and before the payload is exec'd; `prepared` itself was
+    /// resolved by the caller before the fork, so any `Pipe` mode's pipe(2)
+    /// is visible on both sides.
+    pub fn apply_stdio(&self, prepared: &PreparedStdio) -> Result<()> {
+        prepared.apply_to_child()
+    }
+
     /// Indicate that child process has forked the init process to parent process
     pub fn notify_parent(&mut self) -> Result<()> {
         self.parent_channel.send_child_ready()?;
@@ -64,4 +75,14 @@ impl ChildProcess {
         self.parent_channel.wait_for_mapping_ack()?;
         Ok(())
     }
+
+    /// Report the container payload's decoded exit status to the youki main
+    /// process, once the init process has waited on it. `run::Run::exec`
+    /// blocks on the other end of this channel and uses the reported value
+    /// as youki's own process exit code; the `wait` subcommand reads the copy
+    /// the init process writes to disk instead.
+    pub fn report_exit_status(&mut self, exit_code: i32) -> Result<()> {
+        self.parent_channel.send_exit_status(exit_code)?;
+        Ok(())
+    }
 }
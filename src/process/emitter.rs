@@ -0,0 +1,101 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{ErrorKind, Write};
+
+use anyhow::Result;
+use mio::net::UnixStream;
+use mio::Token;
+
+use super::event::Event;
+
+/// A subscriber connection, plus whatever bytes are still queued for it
+/// after a write returned `WouldBlock`.
+struct Subscriber {
+    stream: UnixStream,
+    pending: VecDeque<u8>,
+}
+
+/// Fans lifecycle [`Event`]s out to every subscriber currently connected to
+/// the manager socket, as newline-delimited JSON. The `ChildProcess`/
+/// `ParentChannel` sync machinery, and the manager's own request handling,
+/// feed this as container state transitions are observed, so subscribers
+/// see `created`, `started`, `exec`, `stopped`, `oom` and `exited` as they
+/// happen rather than having to poll.
+///
+/// Subscribers are non-blocking unix sockets, so a slow consumer can make a
+/// write return `WouldBlock`; that's buffered per-subscriber rather than
+/// treated as a disconnect. Call [`Self::flush`] once the manager's poll
+/// loop sees that subscriber's fd become writable again.
+#[derive(Default)]
+pub struct EventEmitter {
+    subscribers: HashMap<Token, Subscriber>,
+}
+
+impl EventEmitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a newly-accepted connection as an event subscriber
+    pub fn subscribe(&mut self, token: Token, stream: UnixStream) {
+        self.subscribers.insert(
+            token,
+            Subscriber {
+                stream,
+                pending: VecDeque::new(),
+            },
+        );
+    }
+
+    pub fn unsubscribe(&mut self, token: Token) {
+        self.subscribers.remove(&token);
+    }
+
+    pub fn is_subscriber(&self, token: Token) -> bool {
+        self.subscribers.contains_key(&token)
+    }
+
+    /// Push `event` to every live subscriber, queuing bytes a non-blocking
+    /// write can't accept right now instead of dropping the subscriber.
+    pub fn emit(&mut self, event: Event) -> Result<()> {
+        let mut line = serde_json::to_vec(&event)?;
+        line.push(b'\n');
+
+        let mut dead = Vec::new();
+        for (token, subscriber) in self.subscribers.iter_mut() {
+            subscriber.pending.extend(line.iter().copied());
+            if flush_subscriber(subscriber).is_err() {
+                dead.push(*token);
+            }
+        }
+        for token in dead {
+            self.subscribers.remove(&token);
+        }
+        Ok(())
+    }
+
+    /// Try to drain a subscriber's queued bytes; call once its fd is
+    /// reported writable by the poller. Drops the subscriber on a real
+    /// write error (as opposed to `WouldBlock`, which just stops early).
+    pub fn flush(&mut self, token: Token) {
+        if let Some(subscriber) = self.subscribers.get_mut(&token) {
+            if flush_subscriber(subscriber).is_err() {
+                self.subscribers.remove(&token);
+            }
+        }
+    }
+}
+
+fn flush_subscriber(subscriber: &mut Subscriber) -> std::result::Result<(), ()> {
+    while !subscriber.pending.is_empty() {
+        let (front, _) = subscriber.pending.as_slices();
+        match subscriber.stream.write(front) {
+            Ok(0) => return Err(()),
+            Ok(n) => {
+                subscriber.pending.drain(..n);
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+            Err(_) => return Err(()),
+        }
+    }
+    Ok(())
+}
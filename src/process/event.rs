@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+/// Structured lifecycle events emitted as containers are created, started,
+/// exec'd into, stopped, OOM-killed, or exit. Pushed to every subscriber of
+/// the manager socket as they happen, so embedders get a live stream
+/// instead of having to poll container state.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    Created { container_id: String },
+    Started { container_id: String, pid: i32 },
+    Exec { container_id: String, pid: i32 },
+    Stopped { container_id: String },
+    Oom { container_id: String },
+    Exited { container_id: String, exit_code: i32 },
+}
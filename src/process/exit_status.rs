@@ -0,0 +1,15 @@
+use nix::sys::wait::WaitStatus;
+
+/// Decode a waited-on exit status the way Unix shells do: a normal exit
+/// returns the code as-is, while a signal-terminated process returns
+/// `128 + signum`. This lets callers tell an OOM kill (SIGKILL -> 137) apart
+/// from a clean exit using a single integer, matching `runc` semantics.
+pub fn decode_exit_status(status: WaitStatus) -> i32 {
+    match status {
+        WaitStatus::Exited(_, code) => code,
+        WaitStatus::Signaled(_, signal, _) => 128 + signal as i32,
+        // stopped/continued/ptrace-event states aren't a final exit; treat
+        // them as an abnormal exit rather than silently returning 0
+        _ => 128,
+    }
+}
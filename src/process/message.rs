@@ -0,0 +1,34 @@
+use std::convert::TryFrom;
+
+use anyhow::{bail, Result};
+
+/// Messages exchanged between the youki process and the forked init
+/// process over the synchronization pipes set up by
+/// [`crate::container::builder::ContainerBuilder::build`].
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// Child process has finished initial setup and is ready to continue
+    ChildReady = 0x01,
+    /// Child is asking the parent to write the uid/gid mapping for its namespace
+    IdentifierMappingRequest = 0x02,
+    /// Parent has finished writing the uid/gid mapping
+    IdentifierMappingAck = 0x03,
+    /// Init process is reporting the container payload's decoded exit status,
+    /// followed by 4 little-endian bytes carrying it
+    ExitStatus = 0x04,
+}
+
+impl TryFrom<u8> for Message {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        Ok(match value {
+            0x01 => Message::ChildReady,
+            0x02 => Message::IdentifierMappingRequest,
+            0x03 => Message::IdentifierMappingAck,
+            0x04 => Message::ExitStatus,
+            _ => bail!("unknown message type received: {}", value),
+        })
+    }
+}
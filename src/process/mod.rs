@@ -0,0 +1,7 @@
+pub mod child;
+pub mod emitter;
+pub mod event;
+pub mod exit_status;
+pub mod message;
+pub mod parent;
+pub mod stdio;
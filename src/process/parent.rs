@@ -0,0 +1,88 @@
+use std::convert::TryFrom;
+use std::io::{Read, Write};
+
+use anyhow::{bail, Result};
+use mio::unix::pipe::{Receiver, Sender};
+
+use super::message::Message;
+
+/// Holds one side of the synchronization pipes: the sending end used to push
+/// messages to the other process, and the receiving end used to read back
+/// its messages. Both youki itself and the init process build one, each
+/// from its own pipe ends.
+pub struct ParentChannel {
+    sender: Sender,
+    receiver: Receiver,
+}
+
+impl ParentChannel {
+    pub fn new(sender: Sender, receiver: Receiver) -> Self {
+        Self { sender, receiver }
+    }
+
+    pub fn send_child_ready(&mut self) -> Result<()> {
+        self.send(Message::ChildReady)
+    }
+
+    pub fn request_identifier_mapping(&mut self) -> Result<()> {
+        self.send(Message::IdentifierMappingRequest)
+    }
+
+    /// Block until the init process reports it's done with its initial setup
+    /// (sent right after the fork, before it forks again to run the
+    /// payload). Must be drained before this channel is treated as carrying
+    /// only the exit status, or [`Self::wait_for_exit_status`] reads this
+    /// byte instead and errors out.
+    pub fn wait_for_child_ready(&mut self) -> Result<()> {
+        match self.recv()? {
+            Message::ChildReady => Ok(()),
+            other => bail!("expected ChildReady, got {:?}", other),
+        }
+    }
+
+    pub fn wait_for_mapping_ack(&mut self) -> Result<()> {
+        match self.recv()? {
+            Message::IdentifierMappingAck => Ok(()),
+            other => bail!("expected IdentifierMappingAck, got {:?}", other),
+        }
+    }
+
+    /// Report the container payload's decoded exit status to whoever is on
+    /// the other end of this channel
+    pub fn send_exit_status(&mut self, exit_code: i32) -> Result<()> {
+        self.send(Message::ExitStatus)?;
+        self.sender.write_all(&exit_code.to_le_bytes())?;
+        Ok(())
+    }
+
+    /// Block until the container payload's exit status is reported over this
+    /// channel, and return it as youki's own process exit code
+    pub fn wait_for_exit_status(&mut self) -> Result<i32> {
+        match self.recv()? {
+            Message::ExitStatus => {
+                let mut buf = [0u8; 4];
+                self.receiver.read_exact(&mut buf)?;
+                Ok(i32::from_le_bytes(buf))
+            }
+            other => bail!("expected ExitStatus, got {:?}", other),
+        }
+    }
+
+    /// Exposes the receiving end so a caller driving its own poll loop (e.g.
+    /// the manager, watching many containers at once) can register it and
+    /// find out when an exit status is ready to read without blocking.
+    pub(crate) fn receiver_mut(&mut self) -> &mut Receiver {
+        &mut self.receiver
+    }
+
+    fn send(&mut self, msg: Message) -> Result<()> {
+        self.sender.write_all(&[msg as u8])?;
+        Ok(())
+    }
+
+    fn recv(&mut self) -> Result<Message> {
+        let mut buf = [0u8; 1];
+        self.receiver.read_exact(&mut buf)?;
+        Message::try_from(buf[0])
+    }
+}
@@ -0,0 +1,192 @@
+use std::os::unix::io::RawFd;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use nix::fcntl::{open, OFlag};
+use nix::sys::stat::Mode;
+use nix::unistd;
+
+/// How a single stdio stream of the container payload should be wired up.
+///
+/// This mirrors the inherit/pipe/null scheme used for the payload's stdio,
+/// plus a fourth option to reuse an fd the caller already has open (e.g. one
+/// end of a pipe it created itself before invoking youki).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StdioMode {
+    /// Leave the fd as whatever youki itself inherited
+    Inherit,
+    /// Point the stream at /dev/null
+    Null,
+    /// Create a pipe; the init process gets one end, the caller gets the other
+    Pipe,
+    /// Dup this already-open fd onto the stream
+    Fd(RawFd),
+}
+
+impl FromStr for StdioMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "inherit" => StdioMode::Inherit,
+            "null" => StdioMode::Null,
+            "pipe" => StdioMode::Pipe,
+            fd => StdioMode::Fd(fd.parse::<RawFd>().with_context(|| {
+                format!(
+                    "invalid stdio mode '{}': expected inherit, null, pipe or a file descriptor number",
+                    fd
+                )
+            })?),
+        })
+    }
+}
+
+/// `--stdin`/`--stdout`/`--stderr` as parsed from the commandline, one mode
+/// per stream.
+#[derive(Debug, Clone, Copy)]
+pub struct StdioConfig {
+    pub stdin: StdioMode,
+    pub stdout: StdioMode,
+    pub stderr: StdioMode,
+}
+
+impl Default for StdioConfig {
+    fn default() -> Self {
+        Self {
+            stdin: StdioMode::Inherit,
+            stdout: StdioMode::Inherit,
+            stderr: StdioMode::Inherit,
+        }
+    }
+}
+
+/// A single stream's wiring, resolved to concrete fds. `Pipe` mode's
+/// pipe(2) call already happened by the time this exists, which is the
+/// whole point: it has to happen before the fork, while there's still only
+/// one process able to see both ends.
+enum PreparedStream {
+    Inherit,
+    Null,
+    Fd(RawFd),
+    Pipe { child_fd: RawFd, parent_fd: RawFd },
+}
+
+/// `StdioConfig` resolved into concrete fds, ready to be applied on one side
+/// of a fork and handed back as [`StdioPipeFds`] on the other.
+pub struct PreparedStdio {
+    stdin: PreparedStream,
+    stdout: PreparedStream,
+    stderr: PreparedStream,
+}
+
+/// The youki-side end of any stream that was set to `Pipe` mode, so the
+/// caller can report it back (printed as an fd number, or passed over the
+/// console/sync socket).
+pub struct StdioPipeFds {
+    pub stdin: Option<RawFd>,
+    pub stdout: Option<RawFd>,
+    pub stderr: Option<RawFd>,
+}
+
+impl StdioPipeFds {
+    /// Report any `Pipe`-mode fds back to the caller, the way `runc` prints
+    /// an allocated console fd. Dropping the owning `Container` does not
+    /// close these fds; they stay open until youki's own process exits.
+    pub fn print(&self) {
+        if let Some(fd) = self.stdin {
+            println!("stdin pipe fd: {}", fd);
+        }
+        if let Some(fd) = self.stdout {
+            println!("stdout pipe fd: {}", fd);
+        }
+        if let Some(fd) = self.stderr {
+            println!("stderr pipe fd: {}", fd);
+        }
+    }
+}
+
+/// Resolve `config` into concrete fds. Must be called in youki's own
+/// process, before `clone`/`fork`: a `Pipe` mode's pipe(2) call made after
+/// the fork would only be visible in whichever process created it, so both
+/// ends have to already exist while parent and child-to-be still share one
+/// fd table.
+pub fn prepare_stdio(config: &StdioConfig) -> Result<PreparedStdio> {
+    Ok(PreparedStdio {
+        stdin: prepare_stream(config.stdin, true)?,
+        stdout: prepare_stream(config.stdout, false)?,
+        stderr: prepare_stream(config.stderr, false)?,
+    })
+}
+
+fn prepare_stream(mode: StdioMode, is_stdin: bool) -> Result<PreparedStream> {
+    Ok(match mode {
+        StdioMode::Inherit => PreparedStream::Inherit,
+        StdioMode::Null => PreparedStream::Null,
+        StdioMode::Fd(fd) => PreparedStream::Fd(fd),
+        StdioMode::Pipe => {
+            let (read_end, write_end) = unistd::pipe().context("failed to create stdio pipe")?;
+            if is_stdin {
+                // init process reads stdin from the pipe; we keep the write end
+                PreparedStream::Pipe { child_fd: read_end, parent_fd: write_end }
+            } else {
+                // init process writes stdout/stderr to the pipe; we keep the read end
+                PreparedStream::Pipe { child_fd: write_end, parent_fd: read_end }
+            }
+        }
+    })
+}
+
+impl PreparedStdio {
+    /// Apply the resolved stdio to fds 0/1/2. Must be called in the init
+    /// process, after `fork`/`clone` and before the payload is exec'd.
+    /// Closes the youki-side end of any pipe, since the init process has no
+    /// use for it.
+    pub fn apply_to_child(&self) -> Result<()> {
+        Self::apply_stream(&self.stdin, unistd::STDIN_FILENO, OFlag::O_RDONLY)?;
+        Self::apply_stream(&self.stdout, unistd::STDOUT_FILENO, OFlag::O_WRONLY)?;
+        Self::apply_stream(&self.stderr, unistd::STDERR_FILENO, OFlag::O_WRONLY)?;
+        Ok(())
+    }
+
+    fn apply_stream(stream: &PreparedStream, target: RawFd, null_flags: OFlag) -> Result<()> {
+        match *stream {
+            PreparedStream::Inherit => {}
+            PreparedStream::Null => {
+                let null_fd = open("/dev/null", null_flags, Mode::empty())
+                    .context("failed to open /dev/null for container stdio")?;
+                unistd::dup2(null_fd, target).context("failed to dup /dev/null onto stdio")?;
+                unistd::close(null_fd)?;
+            }
+            PreparedStream::Fd(fd) => {
+                unistd::dup2(fd, target).context("failed to dup provided fd onto stdio")?;
+            }
+            PreparedStream::Pipe { child_fd, parent_fd } => {
+                unistd::dup2(child_fd, target).context("failed to dup pipe end onto stdio")?;
+                unistd::close(child_fd)?;
+                unistd::close(parent_fd)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Extract the youki-side ends of any pipe-mode streams, closing the
+    /// init-process-side ends this process has no use for. Call this in
+    /// youki's own process, after the fork.
+    pub fn into_parent_fds(self) -> Result<StdioPipeFds> {
+        Ok(StdioPipeFds {
+            stdin: Self::parent_fd(self.stdin)?,
+            stdout: Self::parent_fd(self.stdout)?,
+            stderr: Self::parent_fd(self.stderr)?,
+        })
+    }
+
+    fn parent_fd(stream: PreparedStream) -> Result<Option<RawFd>> {
+        match stream {
+            PreparedStream::Pipe { child_fd, parent_fd } => {
+                unistd::close(child_fd)?;
+                Ok(Some(parent_fd))
+            }
+            _ => Ok(None),
+        }
+    }
+}